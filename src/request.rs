@@ -1,18 +1,339 @@
 use cookie::Cookie;
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
 use http::{HeaderMap, Method, Uri, Version};
 use http_service::Body;
+use mime::Mime;
 use route_recognizer::Params;
 use serde::Deserialize;
 
 use async_std::io::{self, prelude::*};
 use async_std::task::{Context, Poll};
 
+use std::net::{IpAddr, SocketAddr};
 use std::pin::Pin;
+use std::sync::Mutex;
 use std::{str::FromStr, sync::Arc};
 
 use crate::error::Error;
 use crate::middleware::cookies::CookieData;
 
+// Scope note: [`FromRequest`] and its extractors ([`Json`], [`Form`],
+// [`Query`], [`Path`], [`State`]) below are extractor-plumbing only. There
+// is no `Endpoint` impl for handler closures that take these types as
+// arguments, so a route handler still receives the whole `Request` and
+// reaches them only by calling `FromRequest::from_request` itself.
+
+/// A one-shot channel carrying a request's trailing headers, attached to
+/// the underlying `http_service::Request`'s extensions by the HTTP/1.1
+/// chunked decoder once it starts parsing a body whose headers declared a
+/// `Trailer` section. The decoder holds the paired [`oneshot::Sender`] and
+/// fires it once the terminating chunk's trailer section has been parsed.
+///
+/// No decoder in this tree constructs one yet, so [`Request::trailers`]
+/// currently always resolves to an empty `HeaderMap` until that wiring is
+/// added.
+///
+/// The receiver is wrapped in [`futures::future::Shared`] rather than
+/// stored bare, so that [`Request::trailers`] can be awaited more than
+/// once (e.g. by two middlewares) and have every call resolve to the same
+/// headers instead of the first call consuming the channel and every
+/// later call silently seeing an empty map.
+pub(crate) struct Trailers(pub(crate) Mutex<Option<futures::future::Shared<oneshot::Receiver<HeaderMap>>>>);
+
+/// Extracts a typed value out of a [`Request`], the building block for
+/// letting endpoint handlers take extractor arguments instead of the whole
+/// request.
+///
+/// This mirrors actix-web's `FromRequest`. **Scope:** this is the
+/// extractor half only. There is no `Endpoint` impl for handler closures
+/// taking `Json<T>`/`Form<T>`/etc. as arguments, so a handler registered
+/// through the route API receives the whole `Request` and calls
+/// `from_request` itself. The `Option`/`Result` extractors below never
+/// fail: they hand the inner extractor's outcome back to the caller.
+///
+/// The trait carries the borrow's lifetime as a parameter rather than an
+/// associated `Future` type, since extractors like [`Json`] and [`Form`]
+/// need to read the request body asynchronously while holding `&mut
+/// Request<State>`.
+pub trait FromRequest<'a, State>: Sized {
+    /// The error yielded when extraction fails.
+    type Error;
+
+    /// Perform the extraction.
+    fn from_request(req: &'a mut Request<State>) -> BoxFuture<'a, Result<Self, Self::Error>>;
+}
+
+/// Extracts and deserializes the request body as JSON.
+///
+/// See [`Request::body_json`].
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+/// Extracts and deserializes the request body as a url-encoded form.
+///
+/// See [`Request::body_form`].
+#[derive(Debug)]
+pub struct Form<T>(pub T);
+
+/// Extracts and deserializes the request's query string.
+///
+/// See [`Request::query`].
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+/// Extracts the request's named route parameters, deserialized into `T`.
+///
+/// `T` is deserialized from a map of all the current route's named
+/// parameters (e.g. the `:id` in `/posts/:id`), the same source
+/// [`Request::param`] reads from one key at a time. Because that's driven
+/// through [`serde::de::value::MapDeserializer`], `T` must be a
+/// `Deserialize` type that visits a map — a struct with a field per route
+/// parameter (field names matching parameter names), or a
+/// `HashMap<String, String>`. A newtype struct (`struct Id(u32)`) does
+/// *not* work here: its derived `Deserialize` expects a scalar/newtype
+/// visitor, not a map one, and extraction will fail at runtime.
+#[derive(Debug)]
+pub struct Path<T>(pub T);
+
+/// Extracts a clone of the app-global state.
+///
+/// See [`Request::state`].
+#[derive(Debug)]
+pub struct State<S>(pub Arc<S>);
+
+/// The default body size limit used when no [`BodyConfig`] is set: 256kb.
+const DEFAULT_BODY_LIMIT: usize = 256 * 1024;
+
+/// Configuration for reading request bodies via [`Request::body_json`] and
+/// [`Request::body_form`].
+///
+/// Mirrors actix-web's `JsonConfig`: a maximum byte length and an optional
+/// allow-list of accepted `Content-Type`s. Attach one with
+/// [`Request::set_local`] to override the defaults for a route or for the
+/// whole app.
+///
+/// # Examples
+///
+/// ```
+/// use tide::BodyConfig;
+///
+/// let config = BodyConfig::new().limit(1024 * 1024).content_type("application/json");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BodyConfig {
+    limit: usize,
+    content_types: Option<Vec<String>>,
+}
+
+impl Default for BodyConfig {
+    fn default() -> Self {
+        BodyConfig {
+            limit: DEFAULT_BODY_LIMIT,
+            content_types: None,
+        }
+    }
+}
+
+impl BodyConfig {
+    /// Create a new `BodyConfig` with tide's default 256kb limit and no
+    /// `Content-Type` restriction beyond the caller's own default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of body bytes that will be read.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Add an additional `Content-Type` that is accepted, on top of
+    /// `body_json`/`body_form`'s own default. It is compared by type and
+    /// subtype only, ignoring parameters like `charset`.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_types
+            .get_or_insert_with(Vec::new)
+            .push(content_type.into());
+        self
+    }
+}
+
+impl<'a, State, T> FromRequest<'a, State> for Json<T>
+where
+    State: Send + Sync + 'static,
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    type Error = Error;
+
+    fn from_request(req: &'a mut Request<State>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { req.body_json().await.map(Json) })
+    }
+}
+
+impl<'a, State, T> FromRequest<'a, State> for Form<T>
+where
+    State: Send + Sync + 'static,
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    type Error = Error;
+
+    fn from_request(req: &'a mut Request<State>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { req.body_form().await.map(Form) })
+    }
+}
+
+impl<'a, State, T> FromRequest<'a, State> for Query<T>
+where
+    State: Send + Sync + 'static,
+    T: Deserialize<'a> + Send + 'static,
+{
+    type Error = crate::Error;
+
+    fn from_request(req: &'a mut Request<State>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { req.query().map(Query) })
+    }
+}
+
+impl<'a, St, T> FromRequest<'a, St> for Path<T>
+where
+    St: Send + Sync + 'static,
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    type Error = Error;
+
+    fn from_request(req: &'a mut Request<St>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move {
+            let pairs = req.named_params();
+            let deserializer =
+                serde::de::value::MapDeserializer::<_, serde::de::value::Error>::new(
+                    pairs.into_iter(),
+                );
+
+            T::deserialize(deserializer)
+                .map(Path)
+                .map_err(|e| Error::from(crate::Response::new(400).body_string(e.to_string())))
+        })
+    }
+}
+
+impl<'a, St> FromRequest<'a, St> for State<St>
+where
+    St: Send + Sync + 'static,
+{
+    type Error = std::convert::Infallible;
+
+    fn from_request(req: &'a mut Request<St>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { Ok(State(req.state.clone())) })
+    }
+}
+
+impl<'a, St, T> FromRequest<'a, St> for Option<T>
+where
+    St: Send + Sync + 'static,
+    T: FromRequest<'a, St>,
+{
+    type Error = std::convert::Infallible;
+
+    fn from_request(req: &'a mut Request<St>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { Ok(T::from_request(req).await.ok()) })
+    }
+}
+
+impl<'a, St, T> FromRequest<'a, St> for Result<T, T::Error>
+where
+    St: Send + Sync + 'static,
+    T: FromRequest<'a, St>,
+{
+    type Error = std::convert::Infallible;
+
+    fn from_request(req: &'a mut Request<St>) -> BoxFuture<'a, Result<Self, Self::Error>> {
+        Box::pin(async move { Ok(T::from_request(req).await) })
+    }
+}
+
+/// The peer socket address a request arrived from, attached to the
+/// underlying `http_service::Request`'s extensions by the listener.
+///
+/// No listener in this tree constructs one yet, so [`Request::peer_addr`]
+/// currently always reports `None` until that wiring is added.
+pub(crate) struct PeerAddr(pub(crate) SocketAddr);
+
+/// The local socket address a request arrived on, attached to the
+/// underlying `http_service::Request`'s extensions by the listener.
+///
+/// No listener in this tree constructs one yet, so [`Request::local_addr`]
+/// currently always reports `None` until that wiring is added.
+pub(crate) struct LocalAddr(pub(crate) SocketAddr);
+
+/// The set of immediate peers trusted to supply `Forwarded`/
+/// `X-Forwarded-*` headers.
+///
+/// Absent this (the default), [`Request::connection`] and
+/// [`Request::host`] ignore those headers entirely and report the raw
+/// connection instead, since any direct client can set them to spoof its
+/// address and bypass rate-limiting or poison logs. Attach one with
+/// [`Request::set_local`] — typically in the middleware that sits closest
+/// to the listener — once the deployment is known to sit behind one of
+/// these proxies.
+///
+/// Trust is checked against [`Request::peer_addr`], so this has no effect
+/// until a listener also attaches a [`PeerAddr`] extension.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    proxies: Vec<IpAddr>,
+}
+
+impl TrustedProxies {
+    /// Create an empty set of trusted proxies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust an additional proxy's address.
+    pub fn trust(mut self, proxy: IpAddr) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    fn trusts(&self, addr: Option<SocketAddr>) -> bool {
+        match addr {
+            Some(addr) => self.proxies.iter().any(|proxy| *proxy == addr.ip()),
+            None => false,
+        }
+    }
+}
+
+/// Connection information about a request, resolved through reverse-proxy
+/// headers (`Forwarded`, `X-Forwarded-For`, `X-Forwarded-Proto`,
+/// `X-Forwarded-Host`) before falling back to the raw socket.
+///
+/// See [`Request::connection`].
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    scheme: String,
+    host: String,
+    remote: Option<String>,
+}
+
+impl ConnectionInfo {
+    /// The connection scheme, e.g. `http` or `https`.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The host the client believes it is talking to.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The address of the client, resolved from `Forwarded`/`X-Forwarded-For`
+    /// when present, falling back to the raw peer socket address.
+    pub fn remote(&self) -> Option<&str> {
+        self.remote.as_deref()
+    }
+}
+
 /// An HTTP request.
 ///
 /// The `Request` gives endpoints access to basic information about the incoming
@@ -134,7 +455,10 @@ impl<State> Request<State> {
     /// # Ok(()) })}
     /// ```
     pub fn header(&self, key: &'static str) -> Option<&'_ str> {
-        self.request.headers().get(key).map(|h| h.to_str().unwrap())
+        // `HeaderValue::to_str` fails for values that aren't valid visible
+        // ASCII, which is legal on the wire for a client-supplied header —
+        // treat those as absent rather than panicking on hostile input.
+        self.request.headers().get(key).and_then(|h| h.to_str().ok())
     }
 
     /// Get a local value.
@@ -179,6 +503,24 @@ impl<State> Request<State> {
             .parse()
     }
 
+    /// Collects every named route parameter for the current route into
+    /// `(name, value)` pairs, keeping only the innermost router frame's
+    /// value for a name that appears in more than one frame — the same
+    /// precedence [`Request::param`] uses. A serde map deserializer treats
+    /// a repeated key as a hard error, so without this dedup a nested
+    /// route reusing a param name would break [`Path`] extraction instead
+    /// of letting the inner value win.
+    pub(crate) fn named_params(&self) -> Vec<(String, String)> {
+        let mut seen = std::collections::HashSet::new();
+        self.route_params
+            .iter()
+            .rev()
+            .flat_map(|params| params.iter())
+            .filter(|(key, _)| seen.insert((*key).to_owned()))
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect()
+    }
+
     pub(crate) fn rest(&self) -> Option<&str> {
         self.route_params
             .last()
@@ -190,10 +532,15 @@ impl<State> Request<State> {
     /// This method can be called after the body has already been read, but will
     /// produce an empty buffer.
     ///
+    /// The amount read is capped by the [`BodyConfig`] attached via
+    /// [`Request::set_local`] (256kb by default); use
+    /// [`Request::body_bytes_limited`] to override it for a single call.
+    ///
     /// # Errors
     ///
-    /// Any I/O error encountered while reading the body is immediately returned
-    /// as an `Err`.
+    /// Returns a `413 Payload Too Large` error once more bytes than the
+    /// configured limit have been read. Any I/O error encountered while
+    /// reading the body is surfaced as a `500` error.
     ///
     /// # Examples
     ///
@@ -212,12 +559,73 @@ impl<State> Request<State> {
     /// #
     /// # Ok(()) })}
     /// ```
-    pub async fn body_bytes(&mut self) -> std::io::Result<Vec<u8>> {
-        let mut buf = Vec::with_capacity(1024);
-        self.request.body_mut().read_to_end(&mut buf).await?;
+    pub async fn body_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let limit = self.body_limit();
+        self.body_bytes_limited(limit).await
+    }
+
+    /// Reads the entire request body into a byte buffer, overriding the
+    /// [`BodyConfig`] limit for this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `413 Payload Too Large` error once more than `limit` bytes
+    /// have been read. Any I/O error encountered while reading the body is
+    /// surfaced as a `500` error.
+    pub async fn body_bytes_limited(&mut self, limit: usize) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::with_capacity(1024.min(limit));
+        let mut chunk = [0; 8 * 1024];
+        loop {
+            let n = self.request.body_mut().read(&mut chunk).await.map_err(|e| {
+                Error::from(crate::Response::new(500).body_string(e.to_string()))
+            })?;
+            if n == 0 {
+                break;
+            }
+            if buf.len() + n > limit {
+                return Err(Error::from(crate::Response::new(413)));
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
         Ok(buf)
     }
 
+    /// The body size limit configured via [`BodyConfig`], or tide's 256kb
+    /// default if none was set.
+    fn body_limit(&self) -> usize {
+        self.local::<BodyConfig>()
+            .map(|config| config.limit)
+            .unwrap_or(DEFAULT_BODY_LIMIT)
+    }
+
+    /// Returns an `Err` carrying a `415 Unsupported Media Type` response
+    /// unless the request's `Content-Type` matches `default` or one of the
+    /// overrides configured via [`BodyConfig`].
+    fn check_content_type(&self, default: &str) -> Result<(), Error> {
+        let extra_content_types = self
+            .local::<BodyConfig>()
+            .and_then(|config| config.content_types.as_deref())
+            .unwrap_or(&[]);
+
+        let content_type: Option<Mime> = self.header("Content-Type").and_then(|h| h.parse().ok());
+
+        let matches = match &content_type {
+            Some(content_type) => {
+                mime_essence_matches(content_type, default)
+                    || extra_content_types
+                        .iter()
+                        .any(|mime| mime_essence_matches(content_type, mime))
+            }
+            None => false,
+        };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(Error::from(crate::Response::new(415)))
+        }
+    }
+
     /// Reads the entire request body into a string.
     ///
     /// This method can be called after the body has already been read, but will
@@ -225,10 +633,11 @@ impl<State> Request<State> {
     ///
     /// # Errors
     ///
-    /// Any I/O error encountered while reading the body is immediately returned
-    /// as an `Err`.
+    /// Any I/O error or size limit violation is returned as described in
+    /// [`Request::body_bytes`].
     ///
-    /// If the body cannot be interpreted as valid UTF-8, an `Err` is returned.
+    /// If the body cannot be interpreted as valid UTF-8, a `400` error is
+    /// returned.
     ///
     /// # Examples
     ///
@@ -247,23 +656,37 @@ impl<State> Request<State> {
     /// #
     /// # Ok(()) })}
     /// ```
-    pub async fn body_string(&mut self) -> std::io::Result<String> {
+    pub async fn body_string(&mut self) -> Result<String, Error> {
         let body_bytes = self.body_bytes().await?;
-        Ok(String::from_utf8(body_bytes).map_err(|_| std::io::ErrorKind::InvalidData)?)
+        String::from_utf8(body_bytes).map_err(|e| Error::from(crate::Response::new(400).body_string(e.to_string())))
     }
 
     /// Reads and deserialized the entire request body via json.
     ///
     /// # Errors
     ///
-    /// Any I/O error encountered while reading the body is immediately returned
-    /// as an `Err`.
+    /// Returns a `415 Unsupported Media Type` error if the request's
+    /// `Content-Type` is not `application/json` (or a type configured via
+    /// [`BodyConfig`]). Any I/O error or size limit violation is returned as
+    /// described in [`Request::body_bytes`].
     ///
     /// If the body cannot be interpreted as valid json for the target type `T`,
-    /// an `Err` is returned.
-    pub async fn body_json<T: serde::de::DeserializeOwned>(&mut self) -> std::io::Result<T> {
-        let body_bytes = self.body_bytes().await?;
-        Ok(serde_json::from_slice(&body_bytes).map_err(|_| std::io::ErrorKind::InvalidData)?)
+    /// a `400` error is returned.
+    pub async fn body_json<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, Error> {
+        let limit = self.body_limit();
+        self.body_json_limited(limit).await
+    }
+
+    /// Like [`Request::body_json`], but overriding the [`BodyConfig`] byte
+    /// limit for this call.
+    pub async fn body_json_limited<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T, Error> {
+        self.check_content_type("application/json")?;
+        let body_bytes = self.body_bytes_limited(limit).await?;
+        serde_json::from_slice(&body_bytes)
+            .map_err(|e| Error::from(crate::Response::new(400).body_string(e.to_string())))
     }
 
     /// Get the URL querystring.
@@ -281,18 +704,29 @@ impl<State> Request<State> {
     }
 
     /// Parse the request body as a form.
-    pub async fn body_form<T: serde::de::DeserializeOwned>(&mut self) -> io::Result<T> {
-        let body = self
-            .body_bytes()
-            .await
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        let res = serde_qs::from_bytes(&body).map_err(|e| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("could not decode form: {}", e),
-            )
-        })?;
-        Ok(res)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `415 Unsupported Media Type` error if the request's
+    /// `Content-Type` is not `application/x-www-form-urlencoded` (or a type
+    /// configured via [`BodyConfig`]). Any I/O error or size limit violation
+    /// is returned as described in [`Request::body_bytes`].
+    pub async fn body_form<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, Error> {
+        let limit = self.body_limit();
+        self.body_form_limited(limit).await
+    }
+
+    /// Like [`Request::body_form`], but overriding the [`BodyConfig`] byte
+    /// limit for this call.
+    pub async fn body_form_limited<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T, Error> {
+        self.check_content_type("application/x-www-form-urlencoded")?;
+        let body = self.body_bytes_limited(limit).await?;
+        serde_qs::from_bytes(&body).map_err(|e| {
+            Error::from(crate::Response::new(400).body_string(format!("could not decode form: {}", e)))
+        })
     }
 
     /// returns a `Cookie` by name of the cookie.
@@ -304,6 +738,496 @@ impl<State> Request<State> {
         let locked_jar = cookie_data.content.read().unwrap();
         Ok(locked_jar.get(name).cloned())
     }
+
+    /// The peer socket address this request was received from, if the
+    /// listener attached one.
+    ///
+    /// No listener in this tree attaches one yet, so this currently always
+    /// returns `None`.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.request.extensions().get::<PeerAddr>().map(|a| a.0)
+    }
+
+    /// The local socket address this request was received on, if the
+    /// listener attached one.
+    ///
+    /// No listener in this tree attaches one yet, so this currently always
+    /// returns `None`.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.request.extensions().get::<LocalAddr>().map(|a| a.0)
+    }
+
+    /// Resolves to the request's trailing headers once the body stream has
+    /// been fully consumed.
+    ///
+    /// Requests that declared no `Trailer` header resolve to an empty
+    /// `HeaderMap` immediately. Endpoints that stream a body via `Read for
+    /// Request` should fully drain it (e.g. with `body_bytes`) before
+    /// awaiting this, as it resolves only once the HTTP/1.1 chunked
+    /// decoder has parsed the terminating chunk's trailer section;
+    /// awaiting it earlier simply waits rather than deadlocking.
+    pub async fn trailers(&self) -> HeaderMap {
+        // Clone the shared future rather than taking it, so a second call
+        // (from a second middleware, say) gets the same resolved headers
+        // instead of racing the first call for the one-shot channel.
+        let shared = self
+            .request
+            .extensions()
+            .get::<Trailers>()
+            .and_then(|trailers| trailers.0.lock().unwrap().clone());
+
+        match shared {
+            Some(shared) => shared.await.unwrap_or_else(|_| HeaderMap::new()),
+            None => HeaderMap::new(),
+        }
+    }
+
+    /// The host this request claims to be addressed to, preferring
+    /// `X-Forwarded-Host` over the `Host` header and the request URI.
+    ///
+    /// `X-Forwarded-Host` is only honored when the immediate peer is
+    /// listed in a [`TrustedProxies`] attached via [`Request::set_local`];
+    /// otherwise it is ignored, since an untrusted direct client could set
+    /// it itself to spoof the host a middleware or endpoint sees.
+    pub fn host(&self) -> Option<&str> {
+        if self.forwarded_headers_trusted() {
+            if let Some(host) = self.header("X-Forwarded-Host") {
+                return Some(host);
+            }
+        }
+
+        self.header("Host").or_else(|| self.uri().host())
+    }
+
+    /// Extract logical connection information about this request, resolving
+    /// the real scheme, host, and client address through any reverse-proxy
+    /// headers before falling back to the raw socket.
+    ///
+    /// This is useful for middleware that needs to make rate-limiting,
+    /// logging, or TLS-offload-aware redirect decisions based on the
+    /// client's real address rather than that of the nearest proxy.
+    ///
+    /// The `Forwarded`/`X-Forwarded-*` headers are only consulted when the
+    /// immediate peer is listed in a [`TrustedProxies`] attached via
+    /// [`Request::set_local`]; an untrusted direct client can set these
+    /// headers itself, so without an explicit trusted-proxy allowlist they
+    /// are ignored and the raw socket/URI is reported instead.
+    pub fn connection(&self) -> ConnectionInfo {
+        let trusted = self.forwarded_headers_trusted();
+
+        let scheme = trusted
+            .then(|| self.header("X-Forwarded-Proto"))
+            .flatten()
+            .or_else(|| self.uri().scheme_str())
+            .unwrap_or("http")
+            .to_owned();
+
+        let host = self.host().unwrap_or("").to_owned();
+
+        let remote = trusted
+            .then(|| self.forwarded_for())
+            .flatten()
+            .or_else(|| self.peer_addr().map(|addr| addr.to_string()));
+
+        ConnectionInfo {
+            scheme,
+            host,
+            remote,
+        }
+    }
+
+    /// Whether the immediate peer is allowed, via a [`TrustedProxies`]
+    /// attached to this request, to supply `Forwarded`/`X-Forwarded-*`
+    /// headers.
+    fn forwarded_headers_trusted(&self) -> bool {
+        self.local::<TrustedProxies>()
+            .map(|proxies| proxies.trusts(self.peer_addr()))
+            .unwrap_or(false)
+    }
+
+    /// Resolves the logical client address from the `Forwarded` header's
+    /// `for=` directive, falling back to the first entry of
+    /// `X-Forwarded-For`.
+    fn forwarded_for(&self) -> Option<String> {
+        if let Some(forwarded) = self.header("Forwarded") {
+            for part in forwarded.split(';') {
+                let part = part.trim();
+                if let Some(value) = part.strip_prefix("for=") {
+                    return Some(value.trim_matches('"').to_owned());
+                }
+            }
+        }
+
+        self.header("X-Forwarded-For")
+            .and_then(|header| header.split(',').next())
+            .map(|addr| addr.trim().to_owned())
+    }
+
+    /// Parse the request body as `multipart/form-data`, returning a
+    /// [`Multipart`] stream of [`Field`]s.
+    ///
+    /// Each field streams its own body incrementally off the request body:
+    /// while a field is read, at most one read chunk plus a boundary's
+    /// worth of lookahead is buffered, so large uploads can be piped to
+    /// disk without holding the whole file in memory. Scanning for the next
+    /// boundary or a field's headers buffers up to the [`BodyConfig`]
+    /// limit, past which a `413` error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400` error if the `Content-Type` header is missing, is
+    /// not `multipart/form-data`, or carries no `boundary` parameter.
+    pub fn multipart(&mut self) -> Result<Multipart<'_, State>, Error> {
+        let content_type: Mime = self
+            .header("Content-Type")
+            .ok_or_else(|| multipart_error("missing Content-Type header"))?
+            .parse()
+            .map_err(|_| multipart_error("invalid Content-Type header"))?;
+
+        if content_type.essence_str() != mime::MULTIPART_FORM_DATA.essence_str() {
+            return Err(multipart_error("Content-Type is not multipart/form-data"));
+        }
+
+        let boundary = content_type
+            .get_param(mime::BOUNDARY)
+            .ok_or_else(|| multipart_error("multipart/form-data is missing a boundary"))?
+            .as_str()
+            .as_bytes()
+            .to_vec();
+
+        Ok(Multipart::new(self, boundary))
+    }
+
+    /// Parses the `Accept` header into an ordered list of `(Mime, q-value)`
+    /// pairs, sorted by descending quality and, for equal quality, by the
+    /// header's own order.
+    ///
+    /// Returns an empty list if there is no `Accept` header.
+    pub fn accepts(&self) -> Vec<(Mime, f32)> {
+        let header = match self.header("Accept") {
+            Some(header) => header,
+            None => return Vec::new(),
+        };
+
+        let mut accepted: Vec<(Mime, f32)> = header
+            .split(',')
+            .filter_map(|entry| parse_accept_entry(entry.trim()))
+            .collect();
+
+        accepted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        accepted
+    }
+
+    /// Picks the best representation to serve out of `supported`, honoring
+    /// the request's `Accept` header: wildcard matches (`*/*`, `text/*`)
+    /// are considered, ranked by q-value with ties broken by `supported`'s
+    /// own order. A candidate takes the q-value of the most specific range
+    /// that matches it, so `text/html;q=0, */*` rules out `text/html`.
+    ///
+    /// Returns `supported`'s first entry if the request has no `Accept`
+    /// header, and `None` if nothing in `supported` is acceptable.
+    pub fn negotiate(&self, supported: &[Mime]) -> Option<Mime> {
+        let accepted = self.accepts();
+
+        if accepted.is_empty() {
+            return supported.first().cloned();
+        }
+
+        let mut best: Option<(f32, &Mime)> = None;
+
+        for candidate in supported {
+            let q = accepted
+                .iter()
+                .filter(|(mime, _)| mime_matches(mime, candidate))
+                .min_by_key(|(mime, _)| {
+                    let specificity = (mime.type_() != mime::STAR) as u8
+                        + (mime.subtype() != mime::STAR) as u8;
+                    std::cmp::Reverse(specificity)
+                })
+                .map(|(_, q)| *q)
+                .filter(|q| *q > 0.0);
+
+            if let Some(q) = q {
+                let replace = match best {
+                    None => true,
+                    Some((best_q, _)) => q > best_q,
+                };
+                if replace {
+                    best = Some((q, candidate));
+                }
+            }
+        }
+
+        best.map(|(_, mime)| mime.clone())
+    }
+}
+
+fn parse_accept_entry(entry: &str) -> Option<(Mime, f32)> {
+    let mut parts = entry.split(';');
+    let mime: Mime = parts.next()?.trim().parse().ok()?;
+
+    let q = parts
+        .filter_map(|param| {
+            let mut kv = param.splitn(2, '=');
+            let key = kv.next()?.trim();
+            let value = kv.next()?.trim();
+            key.eq_ignore_ascii_case("q").then(|| value.parse().ok())?
+        })
+        .next()
+        .unwrap_or(1.0);
+
+    Some((mime, q))
+}
+
+fn mime_matches(accepted: &Mime, candidate: &Mime) -> bool {
+    let type_matches = accepted.type_() == mime::STAR || accepted.type_() == candidate.type_();
+    let subtype_matches =
+        accepted.subtype() == mime::STAR || accepted.subtype() == candidate.subtype();
+    type_matches && subtype_matches
+}
+
+/// Whether `content_type`'s type/subtype (ignoring parameters like
+/// `charset`) match `expected`, e.g. `"application/json"`.
+///
+/// Used instead of a raw string prefix match so that a type merely
+/// starting with the same characters (`application/json5`,
+/// `application/jsonp`) is correctly rejected.
+fn mime_essence_matches(content_type: &Mime, expected: &str) -> bool {
+    expected
+        .parse::<Mime>()
+        .map(|expected| content_type.essence_str() == expected.essence_str())
+        .unwrap_or(false)
+}
+
+/// A `406 Not Acceptable` error, for endpoints whose [`Request::negotiate`]
+/// call found nothing acceptable to the client.
+pub fn not_acceptable() -> Error {
+    Error::from(crate::Response::new(406))
+}
+
+const MULTIPART_READ_CHUNK: usize = 8 * 1024;
+
+fn multipart_error(msg: &str) -> Error {
+    Error::from(crate::Response::new(400).body_string(msg.to_owned()))
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn parse_field_headers(headers: &[u8]) -> Result<(String, Option<String>, Option<String>), Error> {
+    let text = std::str::from_utf8(headers)
+        .map_err(|_| multipart_error("multipart headers were not valid utf-8"))?;
+
+    let mut name = None;
+    let mut filename = None;
+    let mut content_type = None;
+
+    for line in text.split("\r\n") {
+        let mut parts = line.splitn(2, ':');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts.next().unwrap_or("").trim();
+
+        if key.eq_ignore_ascii_case("Content-Disposition") {
+            for segment in value.split(';').skip(1) {
+                let segment = segment.trim();
+                if let Some(v) = segment.strip_prefix("name=") {
+                    name = Some(v.trim_matches('"').to_owned());
+                } else if let Some(v) = segment.strip_prefix("filename=") {
+                    filename = Some(v.trim_matches('"').to_owned());
+                }
+            }
+        } else if key.eq_ignore_ascii_case("Content-Type") {
+            content_type = Some(value.to_owned());
+        }
+    }
+
+    let name = name.ok_or_else(|| multipart_error("multipart field is missing a name"))?;
+    Ok((name, filename, content_type))
+}
+
+/// A streaming `multipart/form-data` body, returned by [`Request::multipart`].
+///
+/// Call [`Multipart::next_field`] repeatedly to pull each part in turn.
+pub struct Multipart<'r, State> {
+    request: &'r mut Request<State>,
+    boundary: Vec<u8>,
+    buf: Vec<u8>,
+    /// Caps how much of the body `buf` is allowed to buffer while scanning
+    /// for a boundary or a field's headers, reusing the same [`BodyConfig`]
+    /// limit `body_bytes` enforces — otherwise a body missing the expected
+    /// boundary/header-terminator bytes would buffer forever.
+    limit: usize,
+    done: bool,
+}
+
+impl<'r, State> Multipart<'r, State> {
+    fn new(request: &'r mut Request<State>, boundary: Vec<u8>) -> Self {
+        let limit = request.body_limit();
+        Multipart {
+            request,
+            boundary,
+            buf: Vec::new(),
+            limit,
+            done: false,
+        }
+    }
+
+    async fn fill(&mut self) -> Result<bool, Error> {
+        if self.buf.len() >= self.limit {
+            return Err(Error::from(crate::Response::new(413)));
+        }
+
+        let mut chunk = [0; MULTIPART_READ_CHUNK];
+        let n = self
+            .request
+            .request
+            .body_mut()
+            .read(&mut chunk)
+            .await
+            .map_err(|e| Error::from(crate::Response::new(400).body_string(e.to_string())))?;
+        if n == 0 {
+            return Ok(false);
+        }
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(true)
+    }
+
+    /// Read the next field's headers, returning `None` once the terminal
+    /// boundary has been reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400` error on a malformed boundary or if the body ends
+    /// before a field's headers are fully received.
+    pub async fn next_field(&mut self) -> Result<Option<Field<'_, 'r, State>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let opening = [b"--".as_ref(), self.boundary.as_slice()].concat();
+        loop {
+            if let Some(pos) = find_subslice(&self.buf, &opening) {
+                self.buf.drain(..pos + opening.len());
+                break;
+            }
+            if !self.fill().await? {
+                return Err(multipart_error("multipart body ended before boundary"));
+            }
+        }
+
+        loop {
+            if self.buf.len() >= 2 {
+                break;
+            }
+            if !self.fill().await? {
+                return Err(multipart_error("multipart body ended before boundary"));
+            }
+        }
+        if &self.buf[..2] == b"--" {
+            self.done = true;
+            return Ok(None);
+        }
+
+        loop {
+            if let Some(pos) = find_subslice(&self.buf, b"\r\n") {
+                self.buf.drain(..pos + 2);
+                break;
+            }
+            if !self.fill().await? {
+                return Err(multipart_error("multipart body ended before headers"));
+            }
+        }
+
+        let headers = loop {
+            if let Some(pos) = find_subslice(&self.buf, b"\r\n\r\n") {
+                break self.buf.drain(..pos + 4).collect::<Vec<_>>();
+            }
+            if !self.fill().await? {
+                return Err(multipart_error("multipart body ended before headers"));
+            }
+        };
+
+        let (name, filename, content_type) = parse_field_headers(&headers)?;
+
+        Ok(Some(Field {
+            name,
+            filename,
+            content_type,
+            multipart: self,
+        }))
+    }
+}
+
+/// A single field of a `multipart/form-data` request body.
+///
+/// Implements `Read`, streaming the field's body directly off the
+/// underlying connection without buffering the whole part in memory.
+pub struct Field<'m, 'r, State> {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    multipart: &'m mut Multipart<'r, State>,
+}
+
+impl<'m, 'r, State> Field<'m, 'r, State> {
+    /// The field's name, from its `Content-Disposition` header.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The field's original filename, if it was an uploaded file.
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    /// The field's `Content-Type`, if one was sent.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+}
+
+impl<'m, 'r, State> Read for Field<'m, 'r, State> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let multipart = &mut *this.multipart;
+        let delimiter = [b"\r\n--".as_ref(), multipart.boundary.as_slice()].concat();
+
+        loop {
+            if let Some(pos) = find_subslice(&multipart.buf, &delimiter) {
+                let n = pos.min(out.len());
+                out[..n].copy_from_slice(&multipart.buf[..n]);
+                multipart.buf.drain(..n);
+                return Poll::Ready(Ok(n));
+            }
+
+            // No boundary in the buffered data yet: everything but a
+            // delimiter-length tail is safe to hand out, in case the
+            // delimiter straddles this chunk and the next.
+            let safe = multipart.buf.len().saturating_sub(delimiter.len());
+            if safe > 0 {
+                let n = safe.min(out.len());
+                out[..n].copy_from_slice(&multipart.buf[..n]);
+                multipart.buf.drain(..n);
+                return Poll::Ready(Ok(n));
+            }
+
+            let mut chunk = [0; MULTIPART_READ_CHUNK];
+            match Pin::new(multipart.request.request.body_mut()).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => return Poll::Ready(Ok(0)),
+                Poll::Ready(Ok(n)) => multipart.buf.extend_from_slice(&chunk[..n]),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
 }
 
 impl<State> Read for Request<State> {